@@ -0,0 +1,340 @@
+//==============================================================================
+// Boolean CSG (union / difference / intersection) via BSP trees
+//==============================================================================
+//
+// Every `Object` is triangle soup, so the natural representation for a
+// boolean is a `Polygon` (here just a `Vec<Point>`, starting life as a
+// triangle but growing to whatever a plane split leaves behind) plus the
+// plane it lies in. A `BspTree` recursively partitions a solid's
+// polygons with those planes; `clip_to` drops the polygons of one solid
+// that fall inside another, which is all three booleans are built from
+// (see Object::union/difference/intersection below).
+//
+// This follows the classic BSP-csg approach (as popularised by
+// evanw's csg.js): classify, split SPANNING polygons, recurse.
+//==============================================================================
+
+use crate::{Face, Length, Object, Point, Vector};
+
+const EPSILON: Length = 1e-5;
+
+//------------------------------------------------------------------------------
+// Plane
+//------------------------------------------------------------------------------
+
+#[derive(Clone, Copy)]
+struct Plane {
+   normal: Vector,
+   w: Length,
+}
+
+const COPLANAR: i32 = 0;
+const FRONT: i32 = 1;
+const BACK: i32 = 2;
+const SPANNING: i32 = 3;
+
+impl Plane {
+
+   fn from_points(a: Point, b: Point, c: Point) -> Plane {
+      let normal = (b - a).cross(c - a).normalize();
+      Plane { normal, w: normal.dot(a) }
+   }
+
+   fn flip(&mut self) {
+      self.normal = -self.normal;
+      self.w = -self.w;
+   }
+
+   fn distance_to(&self, p: Point) -> Length {
+      self.normal.dot(p) - self.w
+   }
+}
+
+//------------------------------------------------------------------------------
+// Polygon
+//------------------------------------------------------------------------------
+
+#[derive(Clone)]
+struct Polygon {
+   vertices: Vec<Point>,
+   plane: Plane,
+}
+
+impl Polygon {
+
+   fn from_triangle(a: Point, b: Point, c: Point) -> Polygon {
+      Polygon { vertices: vec![a, b, c], plane: Plane::from_points(a, b, c) }
+   }
+
+   fn flip(&mut self) {
+      self.vertices.reverse();
+      self.plane.flip();
+   }
+
+   /// Split this polygon against `plane`, appending whichever of the
+   /// four classification buckets it falls into. A SPANNING polygon is
+   /// cut in two, each piece going to the side it belongs on.
+   fn split(&self, plane: &Plane,
+            coplanar_front: &mut Vec<Polygon>, coplanar_back: &mut Vec<Polygon>,
+            front: &mut Vec<Polygon>, back: &mut Vec<Polygon>) {
+      let mut polygon_type = COPLANAR;
+      let mut vertex_types = Vec::with_capacity(self.vertices.len());
+
+      for &v in &self.vertices {
+         let t = plane.distance_to(v);
+         let vertex_type = if t < -EPSILON { BACK } else if t > EPSILON { FRONT } else { COPLANAR };
+         polygon_type |= vertex_type;
+         vertex_types.push(vertex_type);
+      }
+
+      match polygon_type {
+         COPLANAR => {
+            if plane.normal.dot(self.plane.normal) > 0.0 {
+               coplanar_front.push(self.clone());
+            } else {
+               coplanar_back.push(self.clone());
+            }
+         }
+         FRONT => front.push(self.clone()),
+         BACK => back.push(self.clone()),
+         _ => {
+            let mut front_vertices = Vec::new();
+            let mut back_vertices = Vec::new();
+            let n = self.vertices.len();
+
+            for i in 0..n {
+               let j = (i + 1) % n;
+               let (ti, tj) = (vertex_types[i], vertex_types[j]);
+               let (vi, vj) = (self.vertices[i], self.vertices[j]);
+
+               if ti != BACK { front_vertices.push(vi); }
+               if ti != FRONT { back_vertices.push(vi); }
+
+               if (ti | tj) == SPANNING {
+                  let t = (plane.w - plane.normal.dot(vi)) / plane.normal.dot(vj - vi);
+                  let v = vi + (vj - vi) * t;
+                  front_vertices.push(v);
+                  back_vertices.push(v);
+               }
+            }
+
+            if front_vertices.len() >= 3 {
+               front.push(Polygon { vertices: front_vertices, plane: self.plane });
+            }
+            if back_vertices.len() >= 3 {
+               back.push(Polygon { vertices: back_vertices, plane: self.plane });
+            }
+         }
+      }
+   }
+
+   /// Fan-triangulate back into the `Face`s `Object` expects.
+   fn to_faces(&self) -> Vec<Face> {
+      let mut faces = Vec::with_capacity(self.vertices.len() - 2);
+      for i in 1..self.vertices.len() - 1 {
+         let (a, b, c) = (self.vertices[0], self.vertices[i], self.vertices[i + 1]);
+         // A SPANNING split can interpolate two edges down to (near-)the
+         // same point at the epsilon boundary, leaving a degenerate fan
+         // triangle; skip it rather than hand Face::new a zero-area one.
+         if (b - a).cross(c - a).length() < EPSILON {
+            continue;
+         }
+         faces.push(Face::new(a, b, c));
+      }
+      faces
+   }
+}
+
+//------------------------------------------------------------------------------
+// BSP tree
+//------------------------------------------------------------------------------
+
+struct BspTree {
+   plane: Option<Plane>,
+   front: Option<Box<BspTree>>,
+   back: Option<Box<BspTree>>,
+   polygons: Vec<Polygon>,
+}
+
+impl BspTree {
+
+   fn new() -> BspTree {
+      BspTree { plane: None, front: None, back: None, polygons: Vec::new() }
+   }
+
+   fn build(polygons: &[Polygon]) -> BspTree {
+      let mut tree = BspTree::new();
+      tree.extend(polygons);
+      tree
+   }
+
+   fn extend(&mut self, polygons: &[Polygon]) {
+      if polygons.is_empty() {
+         return;
+      }
+
+      let plane = match self.plane {
+         Some(plane) => plane,
+         None => {
+            self.plane = Some(polygons[0].plane);
+            polygons[0].plane
+         }
+      };
+
+      let mut front = Vec::new();
+      let mut back = Vec::new();
+      let mut coplanar_front = Vec::new();
+      let mut coplanar_back = Vec::new();
+
+      for polygon in polygons {
+         polygon.split(&plane, &mut coplanar_front, &mut coplanar_back, &mut front, &mut back);
+      }
+      self.polygons.extend(coplanar_front);
+      self.polygons.extend(coplanar_back);
+
+      if !front.is_empty() {
+         self.front.get_or_insert_with(|| Box::new(BspTree::new())).extend(&front);
+      }
+      if !back.is_empty() {
+         self.back.get_or_insert_with(|| Box::new(BspTree::new())).extend(&back);
+      }
+   }
+
+   /// Flip every polygon and plane, and swap the front/back children, so
+   /// the tree describes the complement of the solid it held.
+   fn invert(&mut self) {
+      for polygon in &mut self.polygons {
+         polygon.flip();
+      }
+      if let Some(plane) = &mut self.plane {
+         plane.flip();
+      }
+      if let Some(front) = &mut self.front {
+         front.invert();
+      }
+      if let Some(back) = &mut self.back {
+         back.invert();
+      }
+      std::mem::swap(&mut self.front, &mut self.back);
+   }
+
+   /// Drop the parts of `polygons` that lie inside this tree's solid.
+   fn clip_polygons(&self, polygons: &[Polygon]) -> Vec<Polygon> {
+      let plane = match self.plane {
+         Some(plane) => plane,
+         None => return polygons.to_vec(),
+      };
+
+      let mut front = Vec::new();
+      let mut back = Vec::new();
+      let mut coplanar_front = Vec::new();
+      let mut coplanar_back = Vec::new();
+
+      for polygon in polygons {
+         polygon.split(&plane, &mut coplanar_front, &mut coplanar_back, &mut front, &mut back);
+      }
+      front.extend(coplanar_front);
+      back.extend(coplanar_back);
+
+      let mut front = match &self.front {
+         Some(node) => node.clip_polygons(&front),
+         None => front,
+      };
+      let back = match &self.back {
+         Some(node) => node.clip_polygons(&back),
+         None => Vec::new(),
+      };
+
+      front.extend(back);
+      front
+   }
+
+   /// Remove everything in this tree that lies inside `other`.
+   fn clip_to(&mut self, other: &BspTree) {
+      self.polygons = other.clip_polygons(&self.polygons);
+      if let Some(front) = &mut self.front {
+         front.clip_to(other);
+      }
+      if let Some(back) = &mut self.back {
+         back.clip_to(other);
+      }
+   }
+
+   fn all_polygons(&self) -> Vec<Polygon> {
+      let mut polygons = self.polygons.clone();
+      if let Some(front) = &self.front {
+         polygons.extend(front.all_polygons());
+      }
+      if let Some(back) = &self.back {
+         polygons.extend(back.all_polygons());
+      }
+      polygons
+   }
+}
+
+fn object_to_polygons(obj: &Object) -> Vec<Polygon> {
+   obj.faces.iter()
+      .map(|face| Polygon::from_triangle(face.vertex[0], face.vertex[1], face.vertex[2]))
+      .collect()
+}
+
+fn polygons_to_object(polygons: &[Polygon]) -> Object {
+   let mut obj = Object::new();
+   for polygon in polygons {
+      for face in polygon.to_faces() {
+         obj += face;
+      }
+   }
+   obj
+}
+
+impl Object {
+
+   /// The solid occupied by either `self` or `other`.
+   pub(crate) fn union(&self, other: &Object) -> Object {
+      let mut a = BspTree::build(&object_to_polygons(self));
+      let mut b = BspTree::build(&object_to_polygons(other));
+
+      a.clip_to(&b);
+      b.clip_to(&a);
+      b.invert();
+      b.clip_to(&a);
+      b.invert();
+      a.extend(&b.all_polygons());
+
+      polygons_to_object(&a.all_polygons())
+   }
+
+   /// The solid occupied by `self` but not `other`.
+   pub(crate) fn difference(&self, other: &Object) -> Object {
+      let mut a = BspTree::build(&object_to_polygons(self));
+      let mut b = BspTree::build(&object_to_polygons(other));
+
+      a.invert();
+      a.clip_to(&b);
+      b.clip_to(&a);
+      b.invert();
+      b.clip_to(&a);
+      b.invert();
+      a.extend(&b.all_polygons());
+      a.invert();
+
+      polygons_to_object(&a.all_polygons())
+   }
+
+   /// The solid occupied by both `self` and `other`.
+   pub(crate) fn intersection(&self, other: &Object) -> Object {
+      let mut a = BspTree::build(&object_to_polygons(self));
+      let mut b = BspTree::build(&object_to_polygons(other));
+
+      a.invert();
+      b.clip_to(&a);
+      b.invert();
+      a.clip_to(&b);
+      b.clip_to(&a);
+      a.extend(&b.all_polygons());
+      a.invert();
+
+      polygons_to_object(&a.all_polygons())
+   }
+}
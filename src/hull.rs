@@ -0,0 +1,251 @@
+//==============================================================================
+// 3D convex hull via incremental QuickHull
+//==============================================================================
+//
+// Start from a tetrahedron seeded with 4 extreme, non-coplanar points, then
+// repeatedly: pick a face with points still in front of it ("outside" the
+// hull so far), take the farthest of those points, flood-fill from that
+// face across edges to find every other face the point can see (the
+// "horizon" is the boundary between what it can and can't see), delete the
+// visible faces and stitch a new face from each horizon edge to the point.
+// Points orphaned by deleted faces are redistributed among the new faces;
+// points that end up behind every face are inside the hull and dropped.
+//
+// Faces are tombstoned (`None`) rather than removed, so a face's index
+// never changes and the edge map can be updated incrementally instead of
+// rebuilt every iteration.
+//==============================================================================
+
+use std::collections::HashMap;
+
+use crate::{Face, Length, Object, Point};
+
+const EPSILON: Length = 1e-5;
+
+struct HullFace {
+   vertices: [usize; 3],
+   normal: Point,
+   offset: Length,
+   outside: Vec<usize>,
+}
+
+impl HullFace {
+
+   fn new(points: &[Point], a: usize, b: usize, c: usize) -> HullFace {
+      let normal = (points[b] - points[a]).cross(points[c] - points[a]).normalize();
+      HullFace { vertices: [a, b, c], normal, offset: normal.dot(points[a]), outside: Vec::new() }
+   }
+
+   /// Signed distance from `p` to this face's plane; positive means `p`
+   /// is outside the hull on this face's side.
+   fn distance(&self, p: Point) -> Length {
+      self.normal.dot(p) - self.offset
+   }
+}
+
+/// Collapse points within `EPSILON` of one another (quantizing into a grid
+/// of that size, the same approach `Mesh::vertex_index` uses) so near-
+/// duplicate vertices -- e.g. from two objects touching -- don't confuse
+/// the hull.
+fn merge_duplicate_points(points: Vec<Point>) -> Vec<Point> {
+   let scale = 1.0 / EPSILON as f64;
+   let mut seen: HashMap<(i64, i64, i64), ()> = HashMap::new();
+   let mut merged = Vec::new();
+
+   for p in points {
+      let key = ((p.x as f64 * scale).round() as i64,
+                 (p.y as f64 * scale).round() as i64,
+                 (p.z as f64 * scale).round() as i64);
+      if seen.insert(key, ()).is_none() {
+         merged.push(p);
+      }
+   }
+
+   merged
+}
+
+/// Seed the hull with 4 extreme, pairwise non-coplanar points: the min and
+/// max along x, the point farthest from the line through them, and the
+/// point farthest from the plane through all three. `None` if the points
+/// are too few, or all collinear/coplanar.
+fn initial_tetrahedron(points: &[Point]) -> Option<(usize, usize, usize, usize)> {
+   if points.len() < 4 {
+      return None;
+   }
+
+   let (mut a, mut b) = (0, 0);
+   for (i, p) in points.iter().enumerate() {
+      if p.x < points[a].x { a = i; }
+      if p.x > points[b].x { b = i; }
+   }
+   if a == b {
+      return None;
+   }
+
+   let axis = (points[b] - points[a]).normalize();
+   let mut c = None;
+   let mut best = EPSILON;
+   for (i, &p) in points.iter().enumerate() {
+      if i == a || i == b { continue; }
+      let offset = p - points[a];
+      let distance = (offset - axis * offset.dot(axis)).length();
+      if distance > best {
+         best = distance;
+         c = Some(i);
+      }
+   }
+   let c = c?;
+
+   let normal = (points[b] - points[a]).cross(points[c] - points[a]).normalize();
+   let offset = normal.dot(points[a]);
+   let mut d = None;
+   let mut best = EPSILON;
+   for (i, &p) in points.iter().enumerate() {
+      if i == a || i == b || i == c { continue; }
+      let distance = (normal.dot(p) - offset).abs();
+      if distance > best {
+         best = distance;
+         d = Some(i);
+      }
+   }
+   let d = d?;
+
+   Some((a, b, c, d))
+}
+
+/// Build a tetrahedron face `(i, j, k)`, flipping its winding if that
+/// leaves `opposite` (the tetrahedron's 4th point) in front of it.
+fn seed_face(points: &[Point], i: usize, j: usize, k: usize, opposite: Point) -> HullFace {
+   let mut face = HullFace::new(points, i, j, k);
+   if face.distance(opposite) > 0.0 {
+      face.vertices.swap(1, 2);
+      face.normal = -face.normal;
+      face.offset = -face.offset;
+   }
+   face
+}
+
+fn insert_face_edges(edge_map: &mut HashMap<(usize, usize), usize>, index: usize, vertices: [usize; 3]) {
+   let [a, b, c] = vertices;
+   edge_map.insert((a, b), index);
+   edge_map.insert((b, c), index);
+   edge_map.insert((c, a), index);
+}
+
+/// Flood-fill the faces visible from `point`, starting at `start`, and
+/// collect the horizon: the edges (in the visible face's winding order)
+/// where a visible face borders one that isn't.
+fn visible_region(faces: &[Option<HullFace>], edge_map: &HashMap<(usize, usize), usize>,
+                   start: usize, point: Point) -> (Vec<bool>, Vec<(usize, usize)>) {
+   let mut visible = vec![false; faces.len()];
+   visible[start] = true;
+   let mut stack = vec![start];
+   let mut horizon = Vec::new();
+
+   while let Some(f) = stack.pop() {
+      let [a, b, c] = faces[f].as_ref().unwrap().vertices;
+      for &(u, v) in &[(a, b), (b, c), (c, a)] {
+         match edge_map.get(&(v, u)) {
+            Some(&neighbour) if !visible[neighbour]
+               && faces[neighbour].as_ref().unwrap().distance(point) > EPSILON => {
+               visible[neighbour] = true;
+               stack.push(neighbour);
+            }
+            Some(&neighbour) if !visible[neighbour] => horizon.push((u, v)),
+            _ => {}
+         }
+      }
+   }
+
+   (visible, horizon)
+}
+
+fn convex_hull(points: Vec<Point>) -> Object {
+   let points = merge_duplicate_points(points);
+   let (a, b, c, d) = match initial_tetrahedron(&points) {
+      Some(seed) => seed,
+      None => return Object::new(),
+   };
+
+   let mut faces: Vec<Option<HullFace>> = vec![
+      Some(seed_face(&points, a, b, c, points[d])),
+      Some(seed_face(&points, a, c, d, points[b])),
+      Some(seed_face(&points, a, d, b, points[c])),
+      Some(seed_face(&points, b, d, c, points[a])),
+   ];
+
+   let mut edge_map = HashMap::new();
+   for (index, face) in faces.iter().enumerate() {
+      insert_face_edges(&mut edge_map, index, face.as_ref().unwrap().vertices);
+   }
+
+   for (i, &p) in points.iter().enumerate() {
+      if i == a || i == b || i == c || i == d { continue; }
+      if let Some(face) = faces.iter_mut().flatten().find(|face| face.distance(p) > EPSILON) {
+         face.outside.push(i);
+      }
+   }
+
+   while let Some(face_index) = faces.iter()
+      .position(|face| matches!(face, Some(face) if !face.outside.is_empty())) {
+
+      let outside_face = faces[face_index].as_ref().unwrap();
+      let far_point = *outside_face.outside.iter()
+         .max_by(|&&i, &&j| outside_face.distance(points[i])
+            .partial_cmp(&outside_face.distance(points[j])).unwrap())
+         .unwrap();
+
+      let (visible, horizon) = visible_region(&faces, &edge_map, face_index, points[far_point]);
+
+      let mut orphans = Vec::new();
+      for index in 0..faces.len() {
+         if !visible[index] { continue; }
+         if let Some(face) = faces[index].take() {
+            orphans.extend(face.outside.iter().copied().filter(|&p| p != far_point));
+            edge_map.remove(&(face.vertices[0], face.vertices[1]));
+            edge_map.remove(&(face.vertices[1], face.vertices[2]));
+            edge_map.remove(&(face.vertices[2], face.vertices[0]));
+         }
+      }
+
+      let mut new_faces = Vec::new();
+      for (u, v) in horizon {
+         // Skip a (near-)zero-area stitched face, e.g. when far_point is
+         // collinear with the horizon edge -- the same guard
+         // `Object::from_sdf` uses for coincident interpolated points.
+         if (points[v] - points[u]).cross(points[far_point] - points[u]).length() < EPSILON {
+            continue;
+         }
+         let index = faces.len() + new_faces.len();
+         insert_face_edges(&mut edge_map, index, [u, v, far_point]);
+         new_faces.push(HullFace::new(&points, u, v, far_point));
+      }
+      let first_new_face = faces.len();
+      faces.extend(new_faces.into_iter().map(Some));
+
+      for p in orphans {
+         if let Some(face) = faces[first_new_face..].iter_mut().flatten()
+            .find(|face| face.distance(points[p]) > EPSILON) {
+            face.outside.push(p);
+         }
+      }
+   }
+
+   let mut obj = Object::new();
+   for face in faces.into_iter().flatten() {
+      let [a, b, c] = face.vertices;
+      obj += Face::new(points[a], points[b], points[c]);
+   }
+   obj
+}
+
+impl Object {
+
+   /// The convex hull of the combined vertex set of one or more Objects.
+   pub(crate) fn hull(objects: &[Object]) -> Object {
+      let points = objects.iter()
+         .flat_map(|obj| obj.faces.iter().flat_map(|face| face.vertex))
+         .collect();
+      convex_hull(points)
+   }
+}
@@ -11,7 +11,7 @@
 
 #![allow(dead_code)]
 
-use std::ops::{Add, Sub, Neg, Div, AddAssign, MulAssign};
+use std::ops::{Add, Sub, Neg, Div, Mul, AddAssign, MulAssign};
 use std::f32::consts::{PI};
 use std::fs::File;
 
@@ -20,11 +20,21 @@ extern crate itertools;
 use itertools::{zip};
 
 extern crate byteorder;
-use std::io::{Result, Write};
-use byteorder::{LittleEndian, WriteBytesExt};
+use std::io::{Read, Result, Write};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 
 use std::fmt;
 
+mod mesh;
+use mesh::Mesh;
+
+mod sdf;
+
+mod csg;
+
+mod matrix;
+
+mod hull;
 
 
 //==============================================================================
@@ -41,7 +51,7 @@ const FRAGMENTS: u32 = 32;     // number of fragments
 // Geometry
 //==============================================================================
 
-type Length = f32;
+pub(crate) type Length = f32;
 
 
 //------------------------------------------------------------------------------
@@ -49,13 +59,13 @@ type Length = f32;
 //------------------------------------------------------------------------------
 
 #[derive(Clone, Copy)]
-struct Point {
-   x: Length,
-   y: Length,
-   z: Length
+pub(crate) struct Point {
+   pub(crate) x: Length,
+   pub(crate) y: Length,
+   pub(crate) z: Length
 }
 
-type Vector = Point;
+pub(crate) type Vector = Point;
 
 static ORIGIN: Point = Point {x: 0.0, y: 0.0, z: 0.0};
 
@@ -63,27 +73,27 @@ static ORIGIN: Point = Point {x: 0.0, y: 0.0, z: 0.0};
 
 impl Point {
    /// Create a new Point with coordinates `x` `y` and `z`.
-   fn new(x: Length, y: Length, z: Length) -> Point {
+   pub(crate) fn new(x: Length, y: Length, z: Length) -> Point {
       Point{x: x, y: y, z: z}
    }
-   
+
    /// Dot product
-   fn dot(&self, other: Point) -> Length {
+   pub(crate) fn dot(&self, other: Point) -> Length {
       self.x * other.x + self.y * other.y + self.z * other.z
    }
 
    /// Cross product
-   fn cross(self, other: Point) -> Point {
+   pub(crate) fn cross(self, other: Point) -> Point {
       Point{x: self.y*other.z - self.z*other.y,
             y: self.z*other.x - self.x*other.z,
             z: self.x*other.y - self.y*other.x}
    }
 
-   fn length(self) -> Length {
+   pub(crate) fn length(self) -> Length {
       (self.x*self.x + self.y*self.y + self.z*self.z).sqrt()
    }
 
-   fn normalize(self) -> Vector {
+   pub(crate) fn normalize(self) -> Vector {
       self/self.length()
    }
 }
@@ -120,13 +130,21 @@ impl Sub for Point {
 
 impl Div<Length> for Point {
    type Output = Point;
-   
+
    fn div(self, scale: Length) -> Point {
       assert!(scale!=0.0);
       Point {x: self.x/scale, y: self.y/scale, z: self.z/scale}
    }
 }
 
+impl Mul<Length> for Point {
+   type Output = Point;
+
+   fn mul(self, scale: Length) -> Point {
+      Point {x: self.x*scale, y: self.y*scale, z: self.z*scale}
+   }
+}
+
 impl MulAssign<Length> for Point {
 
    fn mul_assign(&mut self, scale: Length) {
@@ -168,28 +186,31 @@ impl fmt::Display for Point
 //------------------------------------------------------------------------------
 
 #[derive(Clone, Copy)]
-struct Face {
-   normal: Vector,
-   vertex: [Point; 3],
-   colour: Colour
+pub(crate) struct Face {
+   pub(crate) normal: Vector,
+   pub(crate) vertex: [Point; 3],
+   pub(crate) colour: Colour
 }
 
 
 impl Face {
-   
-   fn new(p1: Point, p2: Point, p3: Point) -> Face {
+
+   /// Create a new triangular Face from its three corners, in winding
+   /// order, deriving the normal from the triangle itself.
+   pub(crate) fn new(p1: Point, p2: Point, p3: Point) -> Face {
       Face {
-         normal: Vector {x: 0.0, y: 0.0, z: 1.0},
+         normal: (p2-p1).cross(p3-p1).normalize(),
          colour: Colour {r: 0, g: 0, b: 0, alpha: 0},
          vertex: [p1, p2, p3]
       }
    }
-   
-  fn invert(&mut self) {
+
+  pub(crate) fn invert(&mut self) {
     // Swaps points 1 and 2 so that the normal points the other way
     let (p1, p2) = (self.vertex[1], self.vertex[2]);
     self.vertex[1] = p2;
     self.vertex[2] = p1;
+    self.normal = -self.normal;
   }
 }
 
@@ -242,7 +263,7 @@ impl fmt::Display for Face
 
 
 #[derive(Clone, Copy)]
-struct Colour {
+pub(crate) struct Colour {
    r: u8,
    g: u8,
    b: u8,
@@ -251,14 +272,14 @@ struct Colour {
 
 
 #[derive(Clone)]
-struct Object {
-   faces: Vec<Face>
+pub(crate) struct Object {
+   pub(crate) faces: Vec<Face>
 }
 
 
 impl Object {
 
-   fn inverted(&self) -> Object {
+   pub(crate) fn inverted(&self) -> Object {
       let mut obj = self.clone();
       for face in &mut obj.faces {
          face.invert();
@@ -375,6 +396,20 @@ impl Object {
       Object::rectangular_prism(size, size, size)
    }
 
+
+   fn dodecahedron(radius: Length) -> Object {
+      // The dodecahedron is just the dual of the icosahedron.
+      let mut obj = Object::icosahedron(1.0).dual();
+      for face in &mut obj.faces {
+         for vertex in &mut face.vertex {
+            *vertex = vertex.normalize();
+         }
+         face.normal = (face.vertex[1]-face.vertex[0]).cross(face.vertex[2]-face.vertex[0]).normalize();
+      }
+      obj.scale(radius);
+      obj
+   }
+
    
    fn rectangular_prism(width: Length, depth: Length, height: Length) -> Object
    {
@@ -412,6 +447,61 @@ impl Object {
    }
 
 
+   //----------------------------------------------------------------------------
+   // Conway-Hart polyhedron operators
+   //
+   // These round-trip through the shared-vertex `Mesh` representation
+   // (see the `mesh` module), since they need to walk "the faces around
+   // this vertex" or "the two faces either side of this edge", which the
+   // flat triangle soup in `faces` can't answer.
+   //----------------------------------------------------------------------------
+
+   fn to_mesh(&self) -> Mesh {
+      Mesh::from_object(self)
+   }
+
+   fn from_mesh(mesh: &Mesh) -> Object {
+      mesh.to_object()
+   }
+
+   /// Rectification: one new vertex at every edge midpoint.
+   fn ambo(&self) -> Object {
+      Object::from_mesh(&self.to_mesh().ambo())
+   }
+
+   /// Raise a pyramid (optionally offset along the face normal by
+   /// `offset`) on every face.
+   fn kis(&self, offset: Length) -> Object {
+      Object::from_mesh(&self.to_mesh().kis(offset))
+   }
+
+   /// Swap the roles of faces and vertices.
+   fn dual(&self) -> Object {
+      Object::from_mesh(&self.to_mesh().dual())
+   }
+
+   /// Cut every vertex off, replacing it with a face. Dual of `kis`.
+   fn truncate(&self) -> Object {
+      Object::from_mesh(&self.to_mesh().truncate())
+   }
+
+   /// Chiral twist: every face becomes a ring of pentagons.
+   fn gyro(&self) -> Object {
+      Object::from_mesh(&self.to_mesh().gyro())
+   }
+
+   /// Dual of `gyro`.
+   fn snub(&self) -> Object {
+      Object::from_mesh(&self.to_mesh().snub())
+   }
+
+   /// Bevel every edge into its own hexagonal band, shrinking the
+   /// original faces towards their centroid by `amount` (0..1).
+   fn chamfer(&self, amount: Length) -> Object {
+      Object::from_mesh(&self.to_mesh().chamfer(amount))
+   }
+
+
    //----------------------------------------------------------------------------
    // Transformations of Objects
    //----------------------------------------------------------------------------
@@ -439,21 +529,15 @@ impl Object {
    }
 
    
-   fn rotate(self, by: Vector) {}
-   
    fn scale(&mut self, factor: f32) {
       *self *= factor;
    }
 
 
-   fn resize(&self, size: Point) {}
-   fn mirror(&mut self, around: Point) {}
-   // fn multmatrix() {}
    fn color_by_name(&self, colour_name: String, alpha: f32) {}
    fn color(&self, r: f32, g: f32, b: f32, a: f32) {}
    fn offset(&self, r: Length) {}
-   fn hull(&self, other: &Object) {}
-   fn minkowski(&self, other: &Object) {}  
+   fn minkowski(&self, other: &Object) {}
 
 }
 
@@ -527,7 +611,7 @@ type Shape = Object;
 
 impl Shape {
 
-   fn new() -> Shape {
+   pub(crate) fn new() -> Shape {
       Shape{ faces: Vec::new() }
    }
 
@@ -633,9 +717,69 @@ fn write_stl(filename: &str, obj: &Object)  -> std::io::Result<()>
 }
 
 
-fn read_stl(filename: String) -> Option<Object>
+fn read_point(reader: &mut impl Read) -> Result<Point>
+{
+   let x = reader.read_f32::<LittleEndian>()?;
+   let y = reader.read_f32::<LittleEndian>()?;
+   let z = reader.read_f32::<LittleEndian>()?;
+   Ok(Point::new(x, y, z))
+}
+
+
+/// An STL file that couldn't be parsed, either because reading it failed
+/// or because its contents didn't match the format.
+#[derive(Debug)]
+pub(crate) enum StlError {
+   Io(std::io::Error),
+   Malformed(String),
+}
+
+impl From<std::io::Error> for StlError {
+   fn from(err: std::io::Error) -> StlError {
+      StlError::Io(err)
+   }
+}
+
+impl fmt::Display for StlError {
+   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+      match self {
+         StlError::Io(err) => write!(f, "{}", err),
+         StlError::Malformed(msg) => write!(f, "malformed STL file: {}", msg),
+      }
+   }
+}
+
+impl std::error::Error for StlError {}
+
+type StlResult<T> = std::result::Result<T, StlError>;
+
+
+/// Parse already-loaded binary STL bytes (80-byte header, `u32` triangle
+/// count, then per triangle a normal, 3 vertices and an attribute count).
+fn parse_binary_stl(mut bytes: &[u8]) -> StlResult<Object>
 {
-   None
+   let mut header = [0_u8; 80];
+   bytes.read_exact(&mut header)?;
+
+   let triangle_count = bytes.read_u32::<LittleEndian>()?;
+
+   let mut obj = Object::new();
+   for _ in 0..triangle_count {
+      // The stored normal is redundant with the vertices, so skip it and
+      // let Face::new recompute it from the winding order.
+      bytes.read_f32::<LittleEndian>()?;
+      bytes.read_f32::<LittleEndian>()?;
+      bytes.read_f32::<LittleEndian>()?;
+
+      let v0 = read_point(&mut bytes)?;
+      let v1 = read_point(&mut bytes)?;
+      let v2 = read_point(&mut bytes)?;
+      bytes.read_u16::<LittleEndian>()?; // attribute byte count, unused
+
+      obj += Face::new(v0, v1, v2);
+   }
+
+   Ok(obj)
 }
 
 
@@ -644,7 +788,7 @@ fn write_text_stl(filename: &str, obj: &Object) -> std::io::Result<()>
    let mut buffer = File::create(filename)?;
 
    writeln!(buffer, "solid object")?;
-   
+
    for face in &obj.faces {
       writeln!(buffer, "facet normal {normal}", normal=face.normal)?;
       writeln!(buffer, "  outer loop")?;
@@ -654,19 +798,109 @@ fn write_text_stl(filename: &str, obj: &Object) -> std::io::Result<()>
       writeln!(buffer, "  endloop")?;
       writeln!(buffer, "endfacet")?;
    }
-   
+
    writeln!(buffer, "endsolid object")?;
-   
+
    Ok(())
 }
 
 
-fn read_text_stl(filename: String) -> Option<Object>
+fn expect_token<'a>(tokens: &mut impl Iterator<Item = &'a str>, expected: &str) -> StlResult<()>
+{
+   match tokens.next() {
+      Some(token) if token == expected => Ok(()),
+      Some(token) => Err(StlError::Malformed(format!("expected '{}', found '{}'", expected, token))),
+      None => Err(StlError::Malformed(format!("expected '{}', found end of file", expected))),
+   }
+}
+
+
+fn next_f32<'a>(tokens: &mut impl Iterator<Item = &'a str>) -> StlResult<Length>
+{
+   let token = tokens.next()
+      .ok_or_else(|| StlError::Malformed("expected a number, found end of file".to_string()))?;
+   token.parse::<Length>()
+      .map_err(|_| StlError::Malformed(format!("expected a number, found '{}'", token)))
+}
+
+
+/// Parse already-loaded ASCII STL text.
+fn parse_text_stl(text: &str) -> StlResult<Object>
+{
+   let mut tokens = text.split_whitespace().peekable();
+
+   expect_token(&mut tokens, "solid")?;
+   while let Some(&token) = tokens.peek() {
+      if token == "facet" || token == "endsolid" { break; }
+      tokens.next();
+   }
+
+   let mut obj = Object::new();
+   loop {
+      match tokens.next() {
+         Some("endsolid") => break,
+         Some("facet") => {
+            expect_token(&mut tokens, "normal")?;
+            next_f32(&mut tokens)?;
+            next_f32(&mut tokens)?;
+            next_f32(&mut tokens)?;
+
+            expect_token(&mut tokens, "outer")?;
+            expect_token(&mut tokens, "loop")?;
+
+            let mut vertex = [Point::new(0.0, 0.0, 0.0); 3];
+            for v in &mut vertex {
+               expect_token(&mut tokens, "vertex")?;
+               *v = Point::new(next_f32(&mut tokens)?, next_f32(&mut tokens)?, next_f32(&mut tokens)?);
+            }
+
+            expect_token(&mut tokens, "endloop")?;
+            expect_token(&mut tokens, "endfacet")?;
+
+            obj += Face::new(vertex[0], vertex[1], vertex[2]);
+         }
+         Some(other) => return Err(StlError::Malformed(format!("unexpected token '{}'", other))),
+         None => return Err(StlError::Malformed("unexpected end of file".to_string())),
+      }
+   }
+
+   Ok(obj)
+}
+
+
+fn read_text_stl(filename: &str) -> StlResult<Object>
 {
-   None
+   parse_text_stl(&std::fs::read_to_string(filename)?)
 }
 
 
+/// Read an STL file, auto-detecting binary vs. ASCII.
+///
+/// Binary STL has no magic number, so this uses the standard heuristic: a
+/// file is only treated as ASCII if it begins with the token `solid` *and*
+/// its length doesn't match what a binary file with that many triangles
+/// would be (`84 + 50 * n`) — some binary files happen to start with the
+/// bytes "solid" too.
+fn read_stl(filename: &str) -> StlResult<Object>
+{
+   let bytes = std::fs::read(filename)?;
+
+   // A corrupt/adversarial triangle count could overflow the predicted
+   // size; treat that as "doesn't match" rather than panicking.
+   let predicted_binary_len = (bytes.len() >= 84).then(|| {
+      let n = u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]);
+      50_usize.checked_mul(n as usize).and_then(|size| size.checked_add(84))
+   }).flatten();
+
+   if bytes.starts_with(b"solid") && predicted_binary_len != Some(bytes.len()) {
+      let text = String::from_utf8(bytes)
+         .map_err(|err| StlError::Malformed(format!("not valid UTF-8: {}", err)))?;
+      parse_text_stl(&text)
+   } else {
+      parse_binary_stl(&bytes)
+   }
+}
+
 
 //==============================================================================
 // Main
@@ -675,16 +909,299 @@ fn read_text_stl(filename: String) -> Option<Object>
 fn main() {
    println!("Rust CAD v.0.1");
    println!("==============");
- 
+
    /*
    let rectangle = Shape::rectangle(10.0, 20.0);
    println!("{}", rectangle);
    write_text_stl("test.stl", &rectangle);
    */
-   
+
    // let object = Object::cylinder(10.0, 10.0);
    let object = Object::sphere(1.0);
    // println!("{}", circle);
    write_stl("test_bin.stl", &object);
 
+   let hulled = Object::hull(&[Object::cube(2.0), Object::sphere(1.0)]);
+   println!("hull of a cube and a sphere: {} faces", hulled.faces.len());
+}
+
+
+#[cfg(test)]
+mod stl_tests {
+   use super::*;
+
+   #[test]
+   fn binary_round_trip_preserves_sphere_geometry() {
+      let original = Object::sphere(3.0);
+      let path = std::env::temp_dir().join("rscad_round_trip_test.stl");
+      let path = path.to_str().unwrap();
+
+      write_stl(path, &original).unwrap();
+      let read_back = read_stl(path).unwrap();
+
+      assert_eq!(read_back.faces.len(), original.faces.len());
+      for (a, b) in original.faces.iter().zip(read_back.faces.iter()) {
+         for i in 0..3 {
+            assert!((a.vertex[i] - b.vertex[i]).length() < 1e-4);
+         }
+      }
+
+      std::fs::remove_file(path).unwrap();
+   }
+
+   #[test]
+   fn ascii_round_trip_preserves_sphere_geometry() {
+      let original = Object::sphere(3.0);
+      let path = std::env::temp_dir().join("rscad_ascii_round_trip_test.stl");
+      let path = path.to_str().unwrap();
+
+      write_text_stl(path, &original).unwrap();
+      let read_back = read_stl(path).unwrap();
+
+      assert_eq!(read_back.faces.len(), original.faces.len());
+      for (a, b) in original.faces.iter().zip(read_back.faces.iter()) {
+         for i in 0..3 {
+            assert!((a.vertex[i] - b.vertex[i]).length() < 1e-4);
+         }
+      }
+
+      std::fs::remove_file(path).unwrap();
+   }
+}
+
+
+/// Shared helpers for tests that need to assert an Object is a proper
+/// closed solid -- used by `mesh_tests`, `csg_tests`, and anywhere else
+/// that checks an operator or combinator against an analytic volume.
+#[cfg(test)]
+mod solid_test_helpers {
+   use super::*;
+   use std::collections::HashMap;
+
+   fn vertex_key(p: Point) -> (i64, i64, i64) {
+      let scale = 1e5;
+      ((p.x as f64 * scale).round() as i64,
+       (p.y as f64 * scale).round() as i64,
+       (p.z as f64 * scale).round() as i64)
+   }
+
+   /// An Object is a closed (watertight) manifold iff every edge is
+   /// shared by exactly two triangles, once in each winding direction.
+   pub(crate) fn is_closed(obj: &Object) -> bool {
+      let mut edges: HashMap<((i64, i64, i64), (i64, i64, i64)), i32> = HashMap::new();
+      for face in &obj.faces {
+         for i in 0..3 {
+            let a = vertex_key(face.vertex[i]);
+            let b = vertex_key(face.vertex[(i + 1) % 3]);
+            *edges.entry((a, b)).or_insert(0) += 1;
+         }
+      }
+      edges.iter().all(|(&(a, b), &count)| count == 1 && edges.get(&(b, a)) == Some(&1))
+   }
+
+   /// Signed volume via the divergence theorem: the sum, over every
+   /// triangle, of the signed volume of the tetrahedron from the origin.
+   pub(crate) fn signed_volume(obj: &Object) -> Length {
+      obj.faces.iter()
+         .map(|face| face.vertex[0].dot(face.vertex[1].cross(face.vertex[2])))
+         .sum::<Length>() / 6.0
+   }
+}
+
+
+#[cfg(test)]
+mod mesh_tests {
+   use super::*;
+   use super::solid_test_helpers::{is_closed, signed_volume};
+
+   #[test]
+   fn cube_dual_is_a_closed_octahedron_with_positive_volume() {
+      let octahedron = Object::cube(2.0).dual();
+      assert!(is_closed(&octahedron), "dual() should produce a watertight mesh");
+      assert!(signed_volume(&octahedron) > 0.0, "dual() should keep faces wound outward");
+   }
+
+   #[test]
+   fn cube_ambo_is_closed_with_positive_volume() {
+      let cuboctahedron = Object::cube(2.0).ambo();
+      assert!(is_closed(&cuboctahedron), "ambo() should produce a watertight mesh");
+      assert!(signed_volume(&cuboctahedron) > 0.0, "ambo() should keep faces wound outward");
+   }
+
+   #[test]
+   fn cube_kis_is_closed_with_positive_volume() {
+      let kissed = Object::cube(2.0).kis(0.5);
+      assert!(is_closed(&kissed), "kis() should produce a watertight mesh");
+      assert!(signed_volume(&kissed) > 0.0, "kis() should keep faces wound outward");
+   }
+
+   #[test]
+   fn cube_truncate_is_closed_with_positive_volume() {
+      let truncated = Object::cube(2.0).truncate();
+      assert!(is_closed(&truncated), "truncate() should produce a watertight mesh");
+      assert!(signed_volume(&truncated) > 0.0, "truncate() should keep faces wound outward");
+   }
+
+   #[test]
+   fn cube_gyro_is_closed_with_positive_volume() {
+      let gyrated = Object::cube(2.0).gyro();
+      assert!(is_closed(&gyrated), "gyro() should produce a watertight mesh");
+      assert!(signed_volume(&gyrated) > 0.0, "gyro() should keep faces wound outward");
+   }
+
+   #[test]
+   fn cube_snub_is_closed_with_positive_volume() {
+      let snubbed = Object::cube(2.0).snub();
+      assert!(is_closed(&snubbed), "snub() should produce a watertight mesh");
+      assert!(signed_volume(&snubbed) > 0.0, "snub() should keep faces wound outward");
+   }
+
+   #[test]
+   fn cube_chamfer_is_closed_with_positive_volume() {
+      let chamfered = Object::cube(2.0).chamfer(0.3);
+      assert!(is_closed(&chamfered), "chamfer() should produce a watertight mesh");
+      assert!(signed_volume(&chamfered) > 0.0, "chamfer() should keep faces wound outward");
+   }
+}
+
+
+#[cfg(test)]
+mod csg_tests {
+   use super::*;
+   use super::solid_test_helpers::signed_volume;
+
+   /// Two axis-aligned cubes of side 2, offset along x by 1, so they
+   /// overlap in a 1x2x2 slab -- volumes work out to simple numbers.
+   fn overlapping_cubes() -> (Object, Object) {
+      let a = Object::cube(2.0);
+      let mut b = Object::cube(2.0);
+      b += Point::new(1.0, 0.0, 0.0);
+      (a, b)
+   }
+
+   #[test]
+   fn union_of_overlapping_cubes_matches_analytic_volume() {
+      let (a, b) = overlapping_cubes();
+      assert!((signed_volume(&a.union(&b)) - 12.0).abs() < 1e-2);
+   }
+
+   #[test]
+   fn difference_of_overlapping_cubes_matches_analytic_volume() {
+      let (a, b) = overlapping_cubes();
+      assert!((signed_volume(&a.difference(&b)) - 4.0).abs() < 1e-2);
+   }
+
+   #[test]
+   fn intersection_of_overlapping_cubes_matches_analytic_volume() {
+      let (a, b) = overlapping_cubes();
+      assert!((signed_volume(&a.intersection(&b)) - 4.0).abs() < 1e-2);
+   }
+}
+
+
+#[cfg(test)]
+mod sdf_tests {
+   use super::*;
+   use super::solid_test_helpers::{is_closed, signed_volume};
+
+   #[test]
+   fn sphere_is_closed_with_approx_analytic_volume() {
+      let f = sdf::sphere(Point::new(0.0, 0.0, 0.0), 1.5);
+      let obj = Object::from_sdf(f, (Point::new(-2.0, -2.0, -2.0), Point::new(2.0, 2.0, 2.0)), (30, 30, 30));
+      assert!(is_closed(&obj), "sphere SDF mesh should be watertight");
+      let expected = 4.0 / 3.0 * std::f32::consts::PI * 1.5f32.powi(3);
+      assert!((signed_volume(&obj) - expected).abs() / expected < 0.05);
+   }
+
+   #[test]
+   fn cuboid_is_closed_with_approx_analytic_volume() {
+      let f = sdf::cuboid(Point::new(0.0, 0.0, 0.0), Point::new(1.0, 1.0, 1.0));
+      let obj = Object::from_sdf(f, (Point::new(-1.5, -1.5, -1.5), Point::new(1.5, 1.5, 1.5)), (20, 20, 20));
+      assert!(is_closed(&obj), "cuboid SDF mesh should be watertight");
+      assert!((signed_volume(&obj) - 8.0).abs() / 8.0 < 0.05);
+   }
+
+   #[test]
+   fn torus_is_closed_with_approx_analytic_volume() {
+      let f = sdf::torus(Point::new(0.0, 0.0, 0.0), 1.5, 0.5);
+      let obj = Object::from_sdf(f, (Point::new(-2.5, -2.5, -1.0), Point::new(2.5, 2.5, 1.0)), (40, 40, 20));
+      assert!(is_closed(&obj), "torus SDF mesh should be watertight");
+      let expected = 2.0 * std::f32::consts::PI.powi(2) * 1.5 * 0.5f32.powi(2);
+      assert!((signed_volume(&obj) - expected).abs() / expected < 0.1);
+   }
+}
+
+
+#[cfg(test)]
+mod matrix_tests {
+   use super::*;
+
+   fn vertices_match(a: &Object, b: &Object) -> bool {
+      a.faces.len() == b.faces.len() &&
+      a.faces.iter().zip(b.faces.iter())
+         .all(|(fa, fb)| fa.vertex.iter().zip(fb.vertex.iter())
+            .all(|(&va, &vb)| (va - vb).length() < 1e-4))
+   }
+
+   #[test]
+   fn rotate_then_rotate_back_is_identity() {
+      let original = Object::cube(2.0);
+      let mut transformed = original.clone();
+      transformed.rotate(Point::new(0.0, 0.0, 1.0), 1.3);
+      transformed.rotate(Point::new(0.0, 0.0, 1.0), -1.3);
+      assert!(vertices_match(&original, &transformed));
+   }
+
+   #[test]
+   fn mirror_twice_is_identity() {
+      let original = Object::cube(2.0);
+      let mut transformed = original.clone();
+      transformed.mirror(Point::new(1.0, 0.0, 0.0));
+      transformed.mirror(Point::new(1.0, 0.0, 0.0));
+      assert!(vertices_match(&original, &transformed));
+   }
+}
+
+
+#[cfg(test)]
+mod hull_tests {
+   use super::*;
+
+   /// Wrap raw points in throwaway triangles so they can be fed through
+   /// the `Object`-based hull() entry point like real geometry.
+   fn object_from_points(points: &[Point]) -> Object {
+      let mut obj = Object::new();
+      for chunk in points.chunks(3) {
+         let p0 = chunk[0];
+         let p1 = chunk.get(1).copied().unwrap_or(p0);
+         let p2 = chunk.get(2).copied().unwrap_or(p0);
+         obj.faces.push(Face { normal: Point::new(0.0, 0.0, 1.0),
+                                vertex: [p0, p1, p2],
+                                colour: Colour { r: 0, g: 0, b: 0, alpha: 0 } });
+      }
+      obj
+   }
+
+   #[test]
+   fn hull_of_cube_corners_plus_interior_point_drops_the_interior_point() {
+      let corners = [
+         Point::new(0.0, 0.0, 0.0), Point::new(1.0, 0.0, 0.0),
+         Point::new(0.0, 1.0, 0.0), Point::new(1.0, 1.0, 0.0),
+         Point::new(0.0, 0.0, 1.0), Point::new(1.0, 0.0, 1.0),
+         Point::new(0.0, 1.0, 1.0), Point::new(1.0, 1.0, 1.0),
+      ];
+      let interior = Point::new(0.5, 0.5, 0.5);
+
+      let hulled = Object::hull(&[object_from_points(&corners), object_from_points(&[interior])]);
+
+      // A cube hull has 2 triangles per face * 6 faces; the interior
+      // point shouldn't contribute any faces of its own.
+      assert_eq!(hulled.faces.len(), 12);
+
+      for &corner in &corners {
+         assert!(hulled.faces.iter().flat_map(|f| f.vertex.iter())
+            .any(|&v| (v - corner).length() < 1e-4),
+            "hull should include input corner {}", corner);
+      }
+   }
 }
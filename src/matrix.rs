@@ -0,0 +1,156 @@
+//==============================================================================
+// Affine transforms: rotate, mirror, multmatrix, resize
+//==============================================================================
+//
+// `Matrix` is a 4x4 affine transform (the bottom row is always
+// `[0, 0, 0, 1]`, so it's stored but never varies) with `Mul`
+// composition and an `apply(Point)` doing the homogeneous transform.
+// `Object::rotate`/`mirror`/`resize` all funnel through `multmatrix`,
+// which runs every vertex through `apply`, recomputes each face's normal
+// from its transformed vertices and flips winding when the transform's
+// determinant is negative (reflections and negative scales turn the mesh inside out
+// otherwise).
+//==============================================================================
+
+use std::ops::Mul;
+
+use crate::{Length, Object, Point, Vector};
+
+#[derive(Clone, Copy)]
+pub(crate) struct Matrix {
+   m: [[Length; 4]; 4],
+}
+
+impl Matrix {
+
+   pub(crate) fn identity() -> Matrix {
+      Matrix { m: [[1.0, 0.0, 0.0, 0.0],
+                   [0.0, 1.0, 0.0, 0.0],
+                   [0.0, 0.0, 1.0, 0.0],
+                   [0.0, 0.0, 0.0, 1.0]] }
+   }
+
+   pub(crate) fn translation(by: Vector) -> Matrix {
+      Matrix { m: [[1.0, 0.0, 0.0, by.x],
+                   [0.0, 1.0, 0.0, by.y],
+                   [0.0, 0.0, 1.0, by.z],
+                   [0.0, 0.0, 0.0, 1.0]] }
+   }
+
+   pub(crate) fn scaling(factor: Point) -> Matrix {
+      Matrix { m: [[factor.x, 0.0, 0.0, 0.0],
+                   [0.0, factor.y, 0.0, 0.0],
+                   [0.0, 0.0, factor.z, 0.0],
+                   [0.0, 0.0, 0.0, 1.0]] }
+   }
+
+   /// Rodrigues' rotation matrix about unit axis `k` by angle `theta`
+   /// (radians): `R = I cos(theta) + (1 - cos(theta)) k k^T + sin(theta) [k]_x`.
+   pub(crate) fn rotation(axis: Vector, theta: Length) -> Matrix {
+      let k = axis.normalize();
+      let (c, s) = (theta.cos(), theta.sin());
+      let t = 1.0 - c;
+
+      Matrix { m: [[t*k.x*k.x + c,     t*k.x*k.y - s*k.z, t*k.x*k.z + s*k.y, 0.0],
+                   [t*k.x*k.y + s*k.z, t*k.y*k.y + c,     t*k.y*k.z - s*k.x, 0.0],
+                   [t*k.x*k.z - s*k.y, t*k.y*k.z + s*k.x, t*k.z*k.z + c,     0.0],
+                   [0.0,               0.0,               0.0,              1.0]] }
+   }
+
+   /// Reflection through the plane that passes through the origin with
+   /// unit normal `n`: `R = I - 2 n n^T`.
+   pub(crate) fn reflection(normal: Vector) -> Matrix {
+      let n = normal.normalize();
+      Matrix { m: [[1.0 - 2.0*n.x*n.x, -2.0*n.x*n.y,       -2.0*n.x*n.z,       0.0],
+                   [-2.0*n.x*n.y,       1.0 - 2.0*n.y*n.y, -2.0*n.y*n.z,       0.0],
+                   [-2.0*n.x*n.z,      -2.0*n.y*n.z,        1.0 - 2.0*n.z*n.z, 0.0],
+                   [0.0,                0.0,                0.0,               1.0]] }
+   }
+
+   pub(crate) fn apply(&self, p: Point) -> Point {
+      let m = &self.m;
+      Point::new(m[0][0]*p.x + m[0][1]*p.y + m[0][2]*p.z + m[0][3],
+                 m[1][0]*p.x + m[1][1]*p.y + m[1][2]*p.z + m[1][3],
+                 m[2][0]*p.x + m[2][1]*p.y + m[2][2]*p.z + m[2][3])
+   }
+
+   /// Determinant of the top-left 3x3 (the linear part); negative means
+   /// the transform inverts winding.
+   fn determinant(&self) -> Length {
+      let m = &self.m;
+      m[0][0] * (m[1][1]*m[2][2] - m[1][2]*m[2][1])
+    - m[0][1] * (m[1][0]*m[2][2] - m[1][2]*m[2][0])
+    + m[0][2] * (m[1][0]*m[2][1] - m[1][1]*m[2][0])
+   }
+}
+
+impl Mul for Matrix {
+   type Output = Matrix;
+
+   fn mul(self, other: Matrix) -> Matrix {
+      let mut result = [[0.0; 4]; 4];
+      for (row, result_row) in result.iter_mut().enumerate() {
+         for (col, cell) in result_row.iter_mut().enumerate() {
+            *cell = (0..4).map(|k| self.m[row][k] * other.m[k][col]).sum();
+         }
+      }
+      Matrix { m: result }
+   }
+}
+
+impl Object {
+
+   /// Apply an arbitrary affine transform to every vertex, recomputing
+   /// each face's normal from its transformed geometry and flipping
+   /// winding where the transform mirrors the mesh inside out.
+   pub(crate) fn multmatrix(&mut self, matrix: &Matrix) {
+      let flip = matrix.determinant() < 0.0;
+
+      for face in &mut self.faces {
+         for vertex in &mut face.vertex {
+            *vertex = matrix.apply(*vertex);
+         }
+         face.normal = (face.vertex[1]-face.vertex[0]).cross(face.vertex[2]-face.vertex[0]).normalize();
+         if flip {
+            face.invert();
+         }
+      }
+   }
+
+   pub(crate) fn rotate(&mut self, axis: Vector, angle: Length) {
+      assert!(axis.length() > 0.0, "rotate: axis must be non-zero");
+      self.multmatrix(&Matrix::rotation(axis, angle));
+   }
+
+   pub(crate) fn mirror(&mut self, plane_normal: Vector) {
+      self.multmatrix(&Matrix::reflection(plane_normal));
+   }
+
+   /// Axis-aligned bounding box of every vertex, as `(min, max)`.
+   fn bounding_box(&self) -> (Point, Point) {
+      let mut lo = Point::new(Length::INFINITY, Length::INFINITY, Length::INFINITY);
+      let mut hi = Point::new(Length::NEG_INFINITY, Length::NEG_INFINITY, Length::NEG_INFINITY);
+
+      for face in &self.faces {
+         for &v in &face.vertex {
+            lo = Point::new(lo.x.min(v.x), lo.y.min(v.y), lo.z.min(v.z));
+            hi = Point::new(hi.x.max(v.x), hi.y.max(v.y), hi.z.max(v.z));
+         }
+      }
+      (lo, hi)
+   }
+
+   /// Scale each axis independently so the bounding box matches
+   /// `target`; a zero component of `target` leaves that axis unchanged.
+   pub(crate) fn resize(&mut self, target: Point) {
+      let (lo, hi) = self.bounding_box();
+      let size = hi - lo;
+
+      let factor = |target: Length, size: Length| -> Length {
+         if target == 0.0 || size == 0.0 { 1.0 } else { target / size }
+      };
+
+      let scale = Point::new(factor(target.x, size.x), factor(target.y, size.y), factor(target.z, size.z));
+      self.multmatrix(&Matrix::scaling(scale));
+   }
+}
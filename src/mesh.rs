@@ -0,0 +1,503 @@
+//==============================================================================
+// Indexed mesh and Conway-Hart polyhedron operators
+//==============================================================================
+//
+// `Object` stores triangles as a flat `Vec<Face>` with duplicated
+// vertices, which is fine for STL export but useless for topology: you
+// can't ask "what's the next face around this vertex" without knowing
+// which corners of which triangles are actually the same point.
+//
+// `Mesh` is the shared-vertex counterpart: a `Vec<Point>` plus faces
+// stored as index loops (n-gons allowed), in the same spirit as the
+// corner-index `Face` used in easel3d. Conversion from `Object` merges
+// shared vertices and coplanar faces only; the operators that walk the
+// faces or edges around a vertex in rotational order (`ambo`, `dual`,
+// `truncate`) build that adjacency (edge -> faces, vertex -> faces)
+// from the index loops each time they run.
+//==============================================================================
+
+use std::collections::HashMap;
+
+use crate::{Face, Length, Object, Point};
+
+const MERGE_EPSILON: f64 = 1e-5;
+
+pub(crate) struct Mesh {
+   pub(crate) points: Vec<Point>,
+   pub(crate) faces: Vec<Vec<usize>>,
+}
+
+impl Mesh {
+
+   fn new() -> Mesh {
+      Mesh { points: Vec::new(), faces: Vec::new() }
+   }
+
+   //---------------------------------------------------------------------------
+   // Conversion to/from the triangle-soup Object
+   //---------------------------------------------------------------------------
+
+   /// Build an indexed Mesh from a triangle Object, merging vertices that
+   /// land on (almost) the same point, and re-merging coplanar triangles
+   /// that share an edge back into the n-gon they were fan-triangulated
+   /// from (every `Object` constructor splits its faces into triangles,
+   /// so without this the Conway-Hart operators below would see the
+   /// triangulation diagonals as real edges).
+   pub(crate) fn from_object(obj: &Object) -> Mesh {
+      let mut mesh = Mesh::new();
+      let mut index_of: HashMap<(i64, i64, i64), usize> = HashMap::new();
+
+      for face in &obj.faces {
+         let loop_ = face.vertex.iter()
+            .map(|&p| mesh.vertex_index(p, &mut index_of))
+            .collect();
+         mesh.faces.push(loop_);
+      }
+      mesh.merge_coplanar_faces();
+      mesh
+   }
+
+   const COPLANAR_EPSILON: Length = 1e-3;
+
+   fn coplanar(&self, f1: &[usize], f2: &[usize]) -> bool {
+      let n1 = self.face_normal(f1);
+      if n1.dot(self.face_normal(f2)) < 1.0 - Mesh::COPLANAR_EPSILON {
+         return false;
+      }
+      let offset = n1.dot(self.points[f1[0]]);
+      f2.iter().all(|&v| (n1.dot(self.points[v]) - offset).abs() < Mesh::COPLANAR_EPSILON)
+   }
+
+   /// If `f1` and `f2` share a directed edge (one sees `(a, b)`, the
+   /// other `(b, a)`), splice them into the single face loop that
+   /// results from erasing that edge.
+   fn splice(f1: &[usize], f2: &[usize]) -> Option<Vec<usize>> {
+      let (n1, n2) = (f1.len(), f2.len());
+      for i in 0..n1 {
+         let (a, b) = (f1[i], f1[(i + 1) % n1]);
+         let shared = (0..n2).find(|&j| f2[j] == b && f2[(j + 1) % n2] == a);
+         if let Some(j) = shared {
+            let mut merged = Vec::with_capacity(n1 + n2 - 2);
+            merged.extend((0..n1).map(|k| f1[(i + 1 + k) % n1]));
+            merged.extend((1..n2 - 1).map(|k| f2[(j + 1 + k) % n2]));
+            return Some(merged);
+         }
+      }
+      None
+   }
+
+   /// Repeatedly merge any two coplanar faces sharing an edge, until no
+   /// more merges are possible.
+   fn merge_coplanar_faces(&mut self) {
+      let mut faces: Vec<Option<Vec<usize>>> = self.faces.drain(..).map(Some).collect();
+
+      loop {
+         let mut edge_faces: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+         for (index, face) in faces.iter().enumerate() {
+            if let Some(face) = face {
+               let n = face.len();
+               for i in 0..n {
+                  edge_faces.entry(Mesh::edge_key(face[i], face[(i + 1) % n]))
+                     .or_default()
+                     .push(index);
+               }
+            }
+         }
+
+         let merge = edge_faces.values()
+            .filter(|incident| incident.len() == 2)
+            .find_map(|incident| {
+               let (i1, i2) = (incident[0], incident[1]);
+               let f1 = faces[i1].as_ref().unwrap();
+               let f2 = faces[i2].as_ref().unwrap();
+               if self.coplanar(f1, f2) {
+                  Mesh::splice(f1, f2).map(|merged| (i1, i2, merged))
+               } else {
+                  None
+               }
+            });
+
+         match merge {
+            Some((i1, i2, merged)) => {
+               faces[i1] = Some(merged);
+               faces[i2] = None;
+            }
+            None => break,
+         }
+      }
+
+      self.faces = faces.into_iter().flatten().collect();
+   }
+
+   fn vertex_index(&mut self, p: Point, index_of: &mut HashMap<(i64, i64, i64), usize>) -> usize {
+      let scale = 1.0 / MERGE_EPSILON;
+      let key = ((p.x as f64 * scale).round() as i64,
+                 (p.y as f64 * scale).round() as i64,
+                 (p.z as f64 * scale).round() as i64);
+
+      if let Some(&index) = index_of.get(&key) {
+         return index;
+      }
+      let index = self.points.len();
+      self.points.push(p);
+      index_of.insert(key, index);
+      index
+   }
+
+   /// Fan-triangulate every face back into an Object.
+   pub(crate) fn to_object(&self) -> Object {
+      let mut obj = Object::new();
+
+      for face in &self.faces {
+         let p0 = self.points[face[0]];
+         for i in 1..face.len() - 1 {
+            let p1 = self.points[face[i]];
+            let p2 = self.points[face[i + 1]];
+            // A fan triangle can degenerate to zero area (e.g. three
+            // near-collinear points produced by an operator above); skip
+            // it rather than handing Face::new a zero-length cross
+            // product, the same guard csg.rs and hull.rs use.
+            if (p1 - p0).cross(p2 - p0).length() < 1e-9 {
+               continue;
+            }
+            obj += Face::new(p0, p1, p2);
+         }
+      }
+      obj
+   }
+
+   //---------------------------------------------------------------------------
+   // Adjacency
+   //---------------------------------------------------------------------------
+
+   fn edge_key(a: usize, b: usize) -> (usize, usize) {
+      if a < b { (a, b) } else { (b, a) }
+   }
+
+   /// Map from an undirected edge to the faces that use it (length 2 for
+   /// a closed manifold edge, 1 on a boundary).
+   fn build_edge_faces(&self) -> HashMap<(usize, usize), Vec<usize>> {
+      let mut map: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+
+      for (face_index, face) in self.faces.iter().enumerate() {
+         let n = face.len();
+         for i in 0..n {
+            map.entry(Mesh::edge_key(face[i], face[(i + 1) % n]))
+               .or_default()
+               .push(face_index);
+         }
+      }
+      map
+   }
+
+   /// Map from a vertex to the faces that use it, so ring walks don't
+   /// each need a linear scan over every face to find a starting point.
+   fn build_vertex_faces(&self) -> HashMap<usize, Vec<usize>> {
+      let mut map: HashMap<usize, Vec<usize>> = HashMap::new();
+
+      for (face_index, face) in self.faces.iter().enumerate() {
+         for &v in face {
+            map.entry(v).or_default().push(face_index);
+         }
+      }
+      map
+   }
+
+   /// The faces around vertex `v`, in rotational order, found by walking
+   /// from face to face across the edge that ends at `v`.
+   fn vertex_ring(&self, v: usize, edge_faces: &HashMap<(usize, usize), Vec<usize>>,
+                  vertex_faces: &HashMap<usize, Vec<usize>>) -> Vec<usize> {
+      let start = match vertex_faces.get(&v).and_then(|faces| faces.first()) {
+         Some(&f) => f,
+         None => return Vec::new(),
+      };
+
+      let mut ring = vec![start];
+      let mut current = start;
+      loop {
+         let face = &self.faces[current];
+         let n = face.len();
+         let position = face.iter().position(|&x| x == v).unwrap();
+         let prev_vertex = face[(position + n - 1) % n];
+
+         let neighbours = &edge_faces[&Mesh::edge_key(prev_vertex, v)];
+         let next = neighbours.iter().cloned().find(|&f| f != current);
+
+         match next {
+            Some(next) if next != start => { ring.push(next); current = next; }
+            _ => break,
+         }
+      }
+      ring
+   }
+
+   /// The edges incident to vertex `v`, in rotational order, as
+   /// `(neighbour, v)` pairs.
+   fn edge_ring(&self, v: usize, edge_faces: &HashMap<(usize, usize), Vec<usize>>,
+                vertex_faces: &HashMap<usize, Vec<usize>>) -> Vec<(usize, usize)> {
+      let start = match vertex_faces.get(&v).and_then(|faces| faces.first()) {
+         Some(&f) => f,
+         None => return Vec::new(),
+      };
+
+      let mut edges = Vec::new();
+      let mut current = start;
+      loop {
+         let face = &self.faces[current];
+         let n = face.len();
+         let position = face.iter().position(|&x| x == v).unwrap();
+         let prev_vertex = face[(position + n - 1) % n];
+         edges.push((prev_vertex, v));
+
+         let neighbours = &edge_faces[&Mesh::edge_key(prev_vertex, v)];
+         let next = neighbours.iter().cloned().find(|&f| f != current);
+
+         match next {
+            Some(next) if next != start => current = next,
+            _ => break,
+         }
+      }
+      edges
+   }
+
+   fn centroid(&self, face: &[usize]) -> Point {
+      let mut sum = Point::new(0.0, 0.0, 0.0);
+      for &index in face {
+         sum += self.points[index];
+      }
+      sum / (face.len() as Length)
+   }
+
+   fn face_normal(&self, face: &[usize]) -> Point {
+      let p0 = self.points[face[0]];
+      let p1 = self.points[face[1]];
+      let p2 = self.points[face[2]];
+      (p1 - p0).cross(p2 - p0).normalize()
+   }
+
+   //---------------------------------------------------------------------------
+   // Operators
+   //---------------------------------------------------------------------------
+
+   /// Rectification: one new vertex at every edge midpoint; each old
+   /// face becomes a smaller face joining its edge midpoints, and each
+   /// old vertex becomes a new face joining the midpoints of its
+   /// incident edges in rotational order.
+   pub(crate) fn ambo(&self) -> Mesh {
+      let edge_faces = self.build_edge_faces();
+      let vertex_faces = self.build_vertex_faces();
+      let mut points = Vec::with_capacity(edge_faces.len());
+      let mut midpoint_of: HashMap<(usize, usize), usize> = HashMap::new();
+
+      for &edge in edge_faces.keys() {
+         midpoint_of.insert(edge, points.len());
+         points.push((self.points[edge.0] + self.points[edge.1]) / 2.0);
+      }
+
+      let mut faces = Vec::new();
+
+      for face in &self.faces {
+         let n = face.len();
+         faces.push((0..n)
+            .map(|i| midpoint_of[&Mesh::edge_key(face[i], face[(i + 1) % n])])
+            .collect());
+      }
+
+      for v in 0..self.points.len() {
+         let edges = self.edge_ring(v, &edge_faces, &vertex_faces);
+         if edges.len() >= 3 {
+            faces.push(edges.iter()
+               .map(|&(a, b)| midpoint_of[&Mesh::edge_key(a, b)])
+               .collect());
+         }
+      }
+
+      Mesh { points, faces }
+   }
+
+   /// Raise a pyramid on every face: a vertex at the face centroid
+   /// (pushed out along the face normal by `offset`), fan-triangulated
+   /// with the face's original edges.
+   pub(crate) fn kis(&self, offset: Length) -> Mesh {
+      let mut points = self.points.clone();
+      let mut faces = Vec::new();
+
+      for face in &self.faces {
+         let apex = self.centroid(face) + self.face_normal(face) * offset;
+         let apex_index = points.len();
+         points.push(apex);
+
+         let n = face.len();
+         for i in 0..n {
+            faces.push(vec![apex_index, face[i], face[(i + 1) % n]]);
+         }
+      }
+
+      Mesh { points, faces }
+   }
+
+   /// Swap the roles of faces and vertices: one new vertex at the
+   /// centroid of each old face, connected around each old vertex in
+   /// rotational order.
+   pub(crate) fn dual(&self) -> Mesh {
+      let edge_faces = self.build_edge_faces();
+      let vertex_faces = self.build_vertex_faces();
+      let points = self.faces.iter().map(|face| self.centroid(face)).collect();
+
+      let faces = (0..self.points.len())
+         .map(|v| self.vertex_ring(v, &edge_faces, &vertex_faces))
+         .filter(|ring| ring.len() >= 3)
+         .collect();
+
+      Mesh { points, faces }
+   }
+
+   /// Cut every vertex off at a third of the way along each incident
+   /// edge, replacing it with a face. Dual of `kis`.
+   pub(crate) fn truncate(&self) -> Mesh {
+      const T: Length = 1.0 / 3.0;
+
+      let edge_faces = self.build_edge_faces();
+      let vertex_faces = self.build_vertex_faces();
+      let mut points = Vec::new();
+      let mut point_at: HashMap<(usize, usize), usize> = HashMap::new();
+      let mut faces = Vec::new();
+
+      let point_towards = |points: &mut Vec<Point>,
+                               point_at: &mut HashMap<(usize, usize), usize>,
+                               from: usize, to: usize| -> usize {
+         *point_at.entry((from, to)).or_insert_with(|| {
+            points.push(self.points[from] + (self.points[to] - self.points[from]) * T);
+            points.len() - 1
+         })
+      };
+
+      for face in &self.faces {
+         let n = face.len();
+         let mut loop_ = Vec::with_capacity(2 * n);
+         for i in 0..n {
+            let prev = face[(i + n - 1) % n];
+            let v = face[i];
+            let next = face[(i + 1) % n];
+            loop_.push(point_towards(&mut points, &mut point_at, v, prev));
+            loop_.push(point_towards(&mut points, &mut point_at, v, next));
+         }
+         faces.push(loop_);
+      }
+
+      for v in 0..self.points.len() {
+         let ring = self.edge_ring(v, &edge_faces, &vertex_faces);
+         if ring.len() >= 3 {
+            faces.push(ring.iter()
+               .map(|&(neighbour, _)| point_towards(&mut points, &mut point_at, v, neighbour))
+               .collect());
+         }
+      }
+
+      Mesh { points, faces }
+   }
+
+   /// Whether `face`'s loop visits `a` immediately followed by `b` (i.e.
+   /// this face traverses the edge `{a, b}` in the `a -> b` direction).
+   fn face_has_directed_edge(face: &[usize], a: usize, b: usize) -> bool {
+      let n = face.len();
+      (0..n).any(|i| face[i] == a && face[(i + 1) % n] == b)
+   }
+
+   /// Shrink every face towards its centroid by `amount`, and stitch a
+   /// hexagonal band across every original edge to join the shrunk
+   /// copies back together; original vertices are left untouched.
+   pub(crate) fn chamfer(&self, amount: Length) -> Mesh {
+      let mut points = self.points.clone();
+      let mut inset_of: HashMap<(usize, usize), usize> = HashMap::new();
+      let mut faces = Vec::new();
+
+      for (face_index, face) in self.faces.iter().enumerate() {
+         let centroid = self.centroid(face);
+         let mut loop_ = Vec::with_capacity(face.len());
+         for &v in face {
+            let inset_index = points.len();
+            points.push(self.points[v] + (centroid - self.points[v]) * amount);
+            inset_of.insert((face_index, v), inset_index);
+            loop_.push(inset_index);
+         }
+         faces.push(loop_);
+      }
+
+      for (&(a, b), incident) in &self.build_edge_faces() {
+         if incident.len() != 2 {
+            continue;
+         }
+         // `build_edge_faces` records incident faces in face-iteration
+         // order, not by which of them actually traverses `(a, b)` -- so
+         // figure out directionality here instead of assuming
+         // incident[0] is the `b -> a` face. The band below needs f1 to
+         // be the face that sees the edge as `b -> a`, since its inset
+         // points near `a` and `b` continue in that same rotational
+         // direction as the band itself.
+         let (i, j) = (incident[0], incident[1]);
+         let (f1, f2) = if Mesh::face_has_directed_edge(&self.faces[i], a, b) {
+            (j, i)
+         } else {
+            (i, j)
+         };
+         faces.push(vec![
+            a, inset_of[&(f1, a)], inset_of[&(f1, b)],
+            b, inset_of[&(f2, b)], inset_of[&(f2, a)],
+         ]);
+      }
+
+      Mesh { points, faces }
+   }
+
+   /// The two points that trisect edge `{a, b}`, as (near `a`, near `b`),
+   /// allocating them the first time the edge is seen.
+   fn edge_thirds(&self, points: &mut Vec<Point>,
+                  index: &mut HashMap<(usize, usize), (usize, usize)>,
+                  a: usize, b: usize) -> (usize, usize) {
+      let (lo, hi) = if a < b { (a, b) } else { (b, a) };
+      let (near_lo, near_hi) = *index.entry((lo, hi)).or_insert_with(|| {
+         let step = self.points[hi] - self.points[lo];
+         let i1 = points.len();
+         points.push(self.points[lo] + step * (1.0 / 3.0));
+         let i2 = points.len();
+         points.push(self.points[lo] + step * (2.0 / 3.0));
+         (i1, i2)
+      });
+      if a == lo { (near_lo, near_hi) } else { (near_hi, near_lo) }
+   }
+
+   /// Chiral twist: every face centroid gets a pentagon per edge, built
+   /// from asymmetric trisection points of that edge and of the
+   /// preceding one, so adjacent faces interlock with a consistent
+   /// handedness instead of meeting edge-for-edge.
+   pub(crate) fn gyro(&self) -> Mesh {
+      let mut points = self.points.clone();
+      let mut index: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+      let mut faces = Vec::new();
+
+      for face in &self.faces {
+         let centroid_index = points.len();
+         points.push(self.centroid(face));
+
+         let n = face.len();
+         for i in 0..n {
+            let prev = face[(i + n - 1) % n];
+            let a = face[i];
+            let b = face[(i + 1) % n];
+
+            let (_, near_a_in) = self.edge_thirds(&mut points, &mut index, prev, a);
+            let (near_a_out, near_b_out) = self.edge_thirds(&mut points, &mut index, a, b);
+
+            faces.push(vec![centroid_index, near_a_in, a, near_a_out, near_b_out]);
+         }
+      }
+
+      Mesh { points, faces }
+   }
+
+   /// Dual of `gyro`.
+   pub(crate) fn snub(&self) -> Mesh {
+      self.gyro().dual()
+   }
+}